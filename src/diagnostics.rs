@@ -0,0 +1,135 @@
+//! Caret-style diagnostics for malformed folded-stack input lines, similar
+//! in spirit to `codespan-reporting`: each bad line is recorded with enough
+//! context (line number, byte span, reason) to point straight at the
+//! offending text instead of just counting failures.
+
+use std::io::Write;
+
+/// Why a line failed to parse as `stack;frame;... count`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// No space was found to separate the stack from the count.
+    NoSeparator,
+    /// The text after the last space did not parse as a `u64`.
+    BadCount,
+    /// A `perf script` sample block had a header line but no indented
+    /// call-stack frames under it.
+    EmptySample,
+    /// A DTrace-style stack block ended without a trailing count line.
+    MissingCount,
+}
+
+impl Reason {
+    fn message(&self) -> &'static str {
+        match self {
+            Reason::NoSeparator => "no space separator found between stack and count",
+            Reason::BadCount => "count field failed to parse as an integer",
+            Reason::EmptySample => "sample header had no call-stack frames under it",
+            Reason::MissingCount => "stack block had no trailing count line",
+        }
+    }
+}
+
+/// One malformed line, recorded with a 1-based line number and the
+/// byte span (within that line) of the offending region.
+pub struct Diagnostic {
+    pub line_no: usize,
+    pub line: String,
+    pub span: (usize, usize),
+    pub reason: Reason,
+}
+
+impl Diagnostic {
+    pub fn new(line_no: usize, line: &str, span: (usize, usize), reason: Reason) -> Diagnostic {
+        Diagnostic { line_no, line: line.to_string(), span, reason }
+    }
+
+    /// Renders the source line with a caret/underline under `span`, using
+    /// char counts rather than byte counts so multi-byte UTF-8 frame names
+    /// line up correctly.
+    pub fn emit(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let (start, end) = self.span;
+        let prefix_chars = self.line[..start].chars().count();
+        let span_chars = self.line[start..end].chars().count().max(1);
+
+        writeln!(out, "error: {}", self.reason.message())?;
+        writeln!(out, "  --> line {}", self.line_no)?;
+        writeln!(out, "   | {}", self.line)?;
+        writeln!(out, "   | {}{}",
+            " ".repeat(prefix_chars),
+            "^".repeat(span_chars))?;
+        Ok(())
+    }
+}
+
+/// Collects diagnostics as they're found and emits them all at the end of
+/// the run, or bails out immediately on the first one in `--strict` mode.
+pub struct Diagnostics {
+    strict: bool,
+    items: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new(strict: bool) -> Diagnostics {
+        Diagnostics { strict, items: Vec::new() }
+    }
+
+    /// Records a diagnostic. In `--strict` mode this emits it immediately
+    /// and aborts the whole run with a nonzero exit code, since `--strict`
+    /// exists specifically so CI pipelines don't silently drop samples.
+    pub fn push(&mut self, diag: Diagnostic) {
+        if self.strict {
+            let mut stderr = std::io::stderr();
+            let _ = diag.emit(&mut stderr);
+            std::process::exit(1);
+        }
+        self.items.push(diag);
+    }
+
+    /// The reasons recorded so far, in order. Only used by tests that want
+    /// to assert on *why* a parser dropped a line without reaching into
+    /// stderr output.
+    #[cfg(test)]
+    pub(crate) fn reasons(&self) -> Vec<Reason> {
+        self.items.iter().map(|d| d.reason).collect()
+    }
+
+    /// Emits every recorded diagnostic to stderr, followed by a summary
+    /// line, for non-strict runs.
+    pub fn emit_all(&self) {
+        let mut stderr = std::io::stderr();
+        for diag in &self.items {
+            let _ = diag.emit(&mut stderr);
+        }
+        if !self.items.is_empty() {
+            eprintln!("invalid lines: {}", self.items.len());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The caret line must align by char count, not byte count, or a
+    /// multi-byte frame name would shift the `^^^` out from under the
+    /// actual offending text.
+    #[test]
+    fn caret_aligns_on_multibyte_prefix() {
+        let line = "fraction;caf\u{e9};resolve_sym bogus";
+        let last_space = line.rfind(' ').unwrap();
+        let span = (last_space + 1, line.len());
+        let diag = Diagnostic::new(1, line, span, Reason::BadCount);
+
+        let mut out = Vec::new();
+        diag.emit(&mut out).unwrap();
+        let rendered = String::from_utf8(out).unwrap();
+        let caret_line = rendered.lines().last().unwrap();
+
+        let prefix_chars = line[..last_space + 1].chars().count();
+        let expected_prefix = " ".repeat(prefix_chars);
+        assert!(caret_line.starts_with(&format!("   | {}", expected_prefix)));
+        assert!(caret_line.trim_end().ends_with('^'));
+        assert_eq!(caret_line.matches('^').count(), "bogus".chars().count());
+    }
+}
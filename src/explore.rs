@@ -0,0 +1,191 @@
+//! Full-screen terminal browser over a collapsed-stack `Node` tree, used as
+//! an alternative to rendering an SVG for profiles that are too deep or too
+//! large to eyeball as a flamegraph image.
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+use crossterm::event::{self, Event, KeyCode};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, queue, style};
+
+use string_interner::{StringInterner, Sym};
+
+use crate::tree::Node;
+
+/// Cursor position within the list of children currently on screen.
+struct ListState {
+    selected: Option<usize>,
+}
+
+impl ListState {
+    fn new(len: usize) -> ListState {
+        ListState { selected: if len == 0 { None } else { Some(0) } }
+    }
+
+    fn next(&mut self, len: usize) {
+        if len == 0 {
+            self.selected = None;
+            return;
+        }
+        self.selected = Some(match self.selected {
+            Some(i) if i + 1 < len => i + 1,
+            Some(_) => 0,
+            None => 0,
+        });
+    }
+
+    fn prev(&mut self, len: usize) {
+        if len == 0 {
+            self.selected = None;
+            return;
+        }
+        self.selected = Some(match self.selected {
+            Some(0) | None => len - 1,
+            Some(i) => i - 1,
+        });
+    }
+}
+
+/// Spawns the input thread and forwards key events to `tx`. Exits its loop
+/// (and the thread) once a quit key is seen or once `tx` can no longer
+/// send. While `ignore_exit` is set, the quit key is not inspected and every
+/// key is simply forwarded.
+fn spawn_input_thread(tx: mpsc::Sender<KeyCode>, ignore_exit: Arc<AtomicBool>) {
+    thread::spawn(move || {
+        loop {
+            let key = match event::read() {
+                Ok(Event::Key(k)) => k.code,
+                Ok(_) => continue,
+                Err(_) => break,
+            };
+            if !ignore_exit.load(Ordering::SeqCst) {
+                if let KeyCode::Char('q') = key {
+                    let _ = tx.send(key);
+                    break;
+                }
+            }
+            if tx.send(key).is_err() { break; }
+        }
+    });
+}
+
+fn resolve_name(interner: &StringInterner<Sym>, sym: Sym) -> &str {
+    interner.resolve(sym).unwrap_or("?")
+}
+
+/// Walks `root` along `path` and returns the `Node` it points to.
+fn node_at<'a>(root: &'a Node, path: &[Sym]) -> &'a Node {
+    let mut node = root;
+    for key in path {
+        node = &node.children.as_ref().unwrap()[key];
+    }
+    node
+}
+
+fn draw(
+    out: &mut impl Write,
+    interner: &StringInterner<Sym>,
+    root_name: Sym,
+    path: &[Sym],
+    children: &[(Sym, &Node)],
+    parent_count: u64,
+    state: &ListState,
+) -> io::Result<()> {
+    queue!(out, terminal::Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+
+    let mut path_str = String::from(resolve_name(interner, root_name));
+    for key in path {
+        path_str.push('/');
+        path_str.push_str(resolve_name(interner, *key));
+    }
+    queue!(out, style::Print(format!("{}\r\n\r\n", path_str)))?;
+
+    for (i, (key, node)) in children.iter().enumerate() {
+        let name = resolve_name(interner, *key);
+        let percent = if parent_count > 0 {
+            100.0 * (node.count as f64) / (parent_count as f64)
+        } else {
+            0.0
+        };
+        let marker = if state.selected == Some(i) { ">" } else { " " };
+        queue!(out, style::Print(format!(
+            "{} {:<40} {:>10} {:>6.1}%\r\n", marker, name, node.count, percent,
+        )))?;
+    }
+
+    queue!(out, style::Print(
+        "\r\n[up/down] move  [enter] drill in  [esc] up a level  [q] quit\r\n"
+    ))?;
+    out.flush()
+}
+
+/// Runs the interactive explorer until the user quits. `root` is the tree
+/// produced by `Node::add`, `root_name` is the interned "all" symbol used
+/// as the root's label.
+pub fn run(root: &Node, interner: &StringInterner<Sym>, root_name: Sym) -> io::Result<()> {
+    terminal::enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    queue!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let (tx, rx) = mpsc::channel();
+    let ignore_exit = Arc::new(AtomicBool::new(false));
+    spawn_input_thread(tx, ignore_exit.clone());
+
+    // Navigation stack of interned keys, root to current frame.
+    let mut path: Vec<Sym> = Vec::new();
+    let mut states: Vec<ListState> = Vec::new();
+
+    let mut children = root.children_by_count();
+    states.push(ListState::new(children.len()));
+
+    loop {
+        let current = node_at(root, &path);
+        let state = states.last().unwrap();
+        draw(&mut stdout, interner, root_name, &path, &children, current.count, state)?;
+
+        let key = match rx.recv() {
+            Ok(k) => k,
+            Err(_) => break,
+        };
+
+        match key {
+            KeyCode::Char('q') => break,
+            KeyCode::Up => {
+                let len = children.len();
+                states.last_mut().unwrap().prev(len);
+            }
+            KeyCode::Down => {
+                let len = children.len();
+                states.last_mut().unwrap().next(len);
+            }
+            KeyCode::Enter => {
+                if let Some(i) = states.last().unwrap().selected {
+                    let (key, _) = children[i];
+                    path.push(key);
+                    let current = node_at(root, &path);
+                    children = current.children_by_count();
+                    states.push(ListState::new(children.len()));
+                }
+            }
+            KeyCode::Esc => {
+                if path.pop().is_some() {
+                    states.pop();
+                    let current = node_at(root, &path);
+                    children = current.children_by_count();
+                } else {
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    ignore_exit.store(true, Ordering::SeqCst);
+    queue!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    stdout.flush()
+}
@@ -0,0 +1,110 @@
+//! A minimal built-in 3x5 pixel font, just enough to label frames and draw
+//! a status line in the `--view` pixel buffer without pulling in a full
+//! glyph-rasterization crate. Unknown characters (including most
+//! punctuation) fall back to a blank glyph rather than a placeholder box,
+//! since frame names are usually plain identifiers.
+
+pub const GLYPH_W: u32 = 3;
+pub const GLYPH_H: u32 = 5;
+
+const BLANK: [u8; 5] = [0b000, 0b000, 0b000, 0b000, 0b000];
+
+/// Each row is 3 bits, most-significant bit is the leftmost column.
+fn glyph(c: char) -> [u8; 5] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b101, 0b111, 0b111, 0b111, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b111, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b011],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        '(' => [0b001, 0b010, 0b010, 0b010, 0b001],
+        ')' => [0b100, 0b010, 0b010, 0b010, 0b100],
+        '<' => [0b001, 0b010, 0b100, 0b010, 0b001],
+        '>' => [0b100, 0b010, 0b001, 0b010, 0b100],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+        _ => BLANK,
+    }
+}
+
+/// An RGBA8 frame buffer and its dimensions, bundled together since almost
+/// every drawing routine needs all three and passing them separately trips
+/// `clippy::too_many_arguments`.
+pub struct Canvas<'a> {
+    pub frame: &'a mut [u8],
+    pub width: u32,
+    pub height: u32,
+}
+
+impl<'a> Canvas<'a> {
+    pub fn new(frame: &'a mut [u8], width: u32, height: u32) -> Canvas<'a> {
+        Canvas { frame, width, height }
+    }
+
+    /// Blits `text` starting at `(x, y)`, one glyph per
+    /// `(GLYPH_W + 1) * scale` pixels, clipped so nothing is drawn past
+    /// `max_x` or outside the frame bounds.
+    pub fn draw_text(&mut self, x: f32, y: f32, max_x: f32, text: &str, color: [u8; 4], scale: u32) {
+        let (width, height) = (self.width, self.height);
+        let mut cursor_x = x;
+        let advance = ((GLYPH_W + 1) * scale) as f32;
+        for ch in text.chars() {
+            if cursor_x + (GLYPH_W * scale) as f32 > max_x { break; }
+            let bitmap = glyph(ch);
+            for (row, bits) in bitmap.iter().enumerate() {
+                for col in 0..GLYPH_W {
+                    if bits & (1 << (GLYPH_W - 1 - col)) == 0 { continue; }
+                    let px0 = cursor_x + (col * scale) as f32;
+                    let py0 = y + (row as u32 * scale) as f32;
+                    for sy in 0..scale {
+                        for sx in 0..scale {
+                            let px = (px0 + sx as f32) as i64;
+                            let py = (py0 + sy as f32) as i64;
+                            if px < 0 || py < 0 || px as u32 >= width || py as u32 >= height { continue; }
+                            let idx = ((py as u32 * width + px as u32) * 4) as usize;
+                            if idx + 4 <= self.frame.len() {
+                                self.frame[idx..idx + 4].copy_from_slice(&color);
+                            }
+                        }
+                    }
+                }
+            }
+            cursor_x += advance;
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Input-frontend layer that reads several common profiler output
+//! formats and normalizes them into a `(Vec<&str> frames, u64 count)` pair,
+//! the same shape `main()` used to get by hardcoding `rfind(' ')` and
+//! `split(';')` against the folded format. Only the folded format goes
+//! through a `Token` stream; perf and DTrace input are parsed line by line
+//! without producing tokens.
+
+use crate::diagnostics::{Diagnostic, Diagnostics, Reason};
+
+/// A normalized stack sample: an ordered list of frame names (root to leaf)
+/// and how many samples were collapsed into it.
+pub struct Stack<'a> {
+    pub frames: Vec<&'a str>,
+    pub count: u64,
+}
+
+/// Token kinds for a scanned stack. The folded scanner emits only `Frame`,
+/// `Separator` and `Count`; `StackDelimiter` and `Comment` are never produced.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token<'a> {
+    Frame(&'a str),
+    Separator,
+    Count(&'a str),
+    StackDelimiter,
+    Comment(&'a str),
+}
+
+/// Which profiler dumped the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `stack;frame;frame count`, one sample per line.
+    Folded,
+    /// Raw `perf script` output: groups of call-stack lines per sample,
+    /// leaf-to-root, separated by blank lines.
+    Perf,
+    /// DTrace-style stacks, leaf-to-root, separated by blank lines, with a
+    /// trailing count line per stack.
+    Dtrace,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "folded" => Some(Format::Folded),
+            "perf" => Some(Format::Perf),
+            "dtrace" => Some(Format::Dtrace),
+            _ => None,
+        }
+    }
+
+    /// Sniffs the format from the first non-empty line of input.
+    pub fn sniff(sample: &str) -> Format {
+        let mut lines = sample.lines().filter(|l| !l.trim().is_empty());
+        if let Some(first) = lines.next() {
+            // folded format is a single line of "frame;frame;... count" -
+            // check this before the perf heuristic below, since a
+            // single-frame folded stack like "std::foo::bar 42" would
+            // otherwise also satisfy a loose "contains a colon" check.
+            if first.rfind(' ').map_or(false, |i| first[i+1..].parse::<u64>().is_ok()) {
+                return Format::Folded;
+            }
+            // perf script samples start with "comm  pid [cpu] timestamp: ...",
+            // so require the "[cpu]"-shaped bracketed field in addition to
+            // the trailing colon, not just "has a colon, no semicolon".
+            if !first.contains(';') && has_perf_cpu_field(first) {
+                return Format::Perf;
+            }
+        }
+        Format::Dtrace
+    }
+}
+
+/// Looks for perf script's bracketed `[cpu]` field, e.g. in
+/// `"swapper     0 [000]  1317.920001: 100 cycles:ppp:"`.
+fn has_perf_cpu_field(line: &str) -> bool {
+    line.split_whitespace().any(|tok| {
+        let inner = tok.strip_prefix('[').and_then(|t| t.strip_suffix(']'));
+        inner.map_or(false, |digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+    }) && line.contains(':')
+}
+
+/// Tokenizes one folded-format line into `stack;frame;...` frame tokens
+/// plus a trailing count token, or `None` once a `NoSeparator`/`BadCount`
+/// diagnostic has already been recorded for it.
+fn tokenize_folded_line<'a>(
+    line_no: usize,
+    line: &'a str,
+    diagnostics: &mut Diagnostics,
+) -> Option<Vec<Token<'a>>> {
+    let last = match line.rfind(' ') {
+        Some(last) => last,
+        None => {
+            diagnostics.push(Diagnostic::new(line_no, line, (0, line.len()), Reason::NoSeparator));
+            return None;
+        }
+    };
+    let stack = &line[..last];
+    let count_str = &line[last+1..];
+    if count_str.parse::<u64>().is_err() {
+        let span = (last + 1, line.len());
+        diagnostics.push(Diagnostic::new(line_no, line, span, Reason::BadCount));
+        return None;
+    }
+
+    let mut tokens = Vec::new();
+    for (i, frame) in stack.split(';').enumerate() {
+        if i > 0 { tokens.push(Token::Separator); }
+        tokens.push(Token::Frame(frame));
+    }
+    tokens.push(Token::Separator);
+    tokens.push(Token::Count(count_str));
+    Some(tokens)
+}
+
+fn assemble<'a>(tokens: &[Token<'a>]) -> Option<Stack<'a>> {
+    let mut frames = Vec::new();
+    let mut count = None;
+    for tok in tokens {
+        match tok {
+            Token::Frame(f) => frames.push(*f),
+            Token::Count(c) => count = c.parse().ok(),
+            Token::Separator | Token::StackDelimiter | Token::Comment(_) => {}
+        }
+    }
+    count.map(|count| Stack { frames, count })
+}
+
+/// Tokenizes and assembles the folded format, one stack per non-blank
+/// line. Malformed lines are recorded in `diagnostics` and skipped rather
+/// than aborting the whole parse (unless `--strict` is set, in which case
+/// `diagnostics.push` itself exits the process).
+pub fn parse_folded<'a>(input: &'a str, diagnostics: &mut Diagnostics) -> Vec<Stack<'a>> {
+    let mut out = Vec::new();
+    for (line_no, line) in input.lines().enumerate() {
+        let line_no = line_no + 1;
+        let line = line.trim();
+        if line.is_empty() { continue; }
+        if let Some(tokens) = tokenize_folded_line(line_no, line, diagnostics) {
+            if let Some(stack) = assemble(&tokens) {
+                out.push(stack);
+            }
+        }
+    }
+    out
+}
+
+/// Parses `perf script` output: consecutive non-blank, indented lines form
+/// one sample's call stack (innermost frame first), blank lines delimit
+/// samples. Frames are reversed to root-to-leaf order and identical
+/// stacks are coalesced into counts. A sample header with no frames under
+/// it doesn't fit the grammar and is recorded as an `EmptySample`
+/// diagnostic rather than silently dropped.
+pub fn parse_perf<'a>(input: &'a str, diagnostics: &mut Diagnostics) -> Vec<Stack<'a>> {
+    let mut samples: Vec<Vec<&str>> = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut header: Option<(usize, &str)> = None;
+
+    let mut flush = |current: &mut Vec<&'a str>, header: &mut Option<(usize, &'a str)>, samples: &mut Vec<Vec<&'a str>>, diagnostics: &mut Diagnostics| {
+        if !current.is_empty() {
+            samples.push(std::mem::take(current));
+        } else if let Some((line_no, line)) = header.take() {
+            diagnostics.push(Diagnostic::new(line_no, line, (0, line.len()), Reason::EmptySample));
+        }
+        *header = None;
+    };
+
+    for (line_no, line) in input.lines().enumerate() {
+        let line_no = line_no + 1;
+        if line.trim().is_empty() {
+            flush(&mut current, &mut header, &mut samples, diagnostics);
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            // a new sample's header line; flush whatever came before it.
+            flush(&mut current, &mut header, &mut samples, diagnostics);
+            header = Some((line_no, line));
+            continue;
+        }
+        let frame = line.trim().split_whitespace().nth(1).unwrap_or(line.trim());
+        current.push(frame);
+    }
+    flush(&mut current, &mut header, &mut samples, diagnostics);
+
+    coalesce(samples.into_iter().map(|mut frames| {
+        frames.reverse();
+        frames
+    }))
+}
+
+/// Parses DTrace-style `ustack()`/`kstack()` aggregations: stacks are
+/// leaf-to-root, separated by blank lines, each followed by a trailing
+/// count line (just an integer). A block that ends without a count line
+/// is recorded as a `MissingCount` diagnostic and dropped.
+pub fn parse_dtrace<'a>(input: &'a str, diagnostics: &mut Diagnostics) -> Vec<Stack<'a>> {
+    let mut out = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    // Line number of the last frame pushed onto `current`, the line the
+    // `MissingCount` diagnostic shows.
+    let mut last_line: Option<usize> = None;
+
+    for (line_no, line) in input.lines().enumerate() {
+        let line_no = line_no + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            if let Some(&last) = current.last() {
+                let no = last_line.unwrap_or(line_no);
+                diagnostics.push(Diagnostic::new(no, last, (0, last.len()), Reason::MissingCount));
+            }
+            current.clear();
+            last_line = None;
+            continue;
+        }
+        if let Ok(count) = trimmed.parse::<u64>() {
+            current.reverse();
+            out.push(Stack { frames: std::mem::take(&mut current), count });
+            last_line = None;
+        } else {
+            current.push(trimmed);
+            last_line = Some(line_no);
+        }
+    }
+    if let Some(&last) = current.last() {
+        let no = last_line.unwrap_or(0);
+        diagnostics.push(Diagnostic::new(no, last, (0, last.len()), Reason::MissingCount));
+    }
+    out
+}
+
+/// Coalesces identical stacks into counts while preserving the order each
+/// distinct stack was first seen in, so identical input always yields an
+/// identical layout (a plain `HashMap` would shuffle it by the process's
+/// randomized hash seed).
+fn coalesce<'a>(stacks: impl Iterator<Item = Vec<&'a str>>) -> Vec<Stack<'a>> {
+    let mut index: std::collections::HashMap<Vec<&'a str>, usize> = std::collections::HashMap::new();
+    let mut order: Vec<Vec<&'a str>> = Vec::new();
+    let mut counts: Vec<u64> = Vec::new();
+    for frames in stacks {
+        if let Some(&i) = index.get(&frames) {
+            counts[i] += 1;
+        } else {
+            index.insert(frames.clone(), order.len());
+            order.push(frames);
+            counts.push(1);
+        }
+    }
+    order.into_iter().zip(counts).map(|(frames, count)| Stack { frames, count }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniff_single_frame_folded_stack_is_not_perf() {
+        // one frame, no ';', but contains "::" - must not be mistaken for a
+        // perf header just because it has colons in it.
+        let sample = "std::collections::HashMap::insert 42";
+        assert_eq!(Format::sniff(sample), Format::Folded);
+    }
+
+    #[test]
+    fn sniff_perf_header_is_perf() {
+        let sample = "swapper     0 [000]  1317.920001: 100 cycles:ppp:\n\tresolve_sym\n";
+        assert_eq!(Format::sniff(sample), Format::Perf);
+    }
+
+    #[test]
+    fn perf_header_with_no_frames_is_empty_sample() {
+        let input = "swapper     0 [000]  1317.920001: 100 cycles:ppp:\n\n";
+        let mut diagnostics = Diagnostics::new(false);
+        let stacks = parse_perf(input, &mut diagnostics);
+        assert!(stacks.is_empty());
+        assert_eq!(diagnostics.reasons(), vec![Reason::EmptySample]);
+    }
+
+    #[test]
+    fn dtrace_block_missing_count_line_is_missing_count() {
+        let input = "resolve_sym\nmain\n\n";
+        let mut diagnostics = Diagnostics::new(false);
+        let stacks = parse_dtrace(input, &mut diagnostics);
+        assert!(stacks.is_empty());
+        assert_eq!(diagnostics.reasons(), vec![Reason::MissingCount]);
+    }
+}
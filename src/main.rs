@@ -1,197 +1,82 @@
 extern crate string_interner;
 mod xml_quote;
 mod num_fmt;
+mod tree;
+mod explore;
+mod diagnostics;
+mod view;
+mod input;
+mod font;
 use string_interner::{StringInterner, Sym};
 
-use std::io::{self, BufRead, BufReader};
+use std::io::{self, Read};
 use std::fmt::{Write};
-use std::collections::{HashMap};
 use xml_quote::{XmlQuote};
 use num_fmt::{NumFmt};
-
-#[derive(Debug)]
-struct Node {
-    count: u64,
-    children: Option<HashMap<Sym, Node>>,
-}
-
-impl Node {
-    pub fn new() -> Node {
-        Node {
-            count: 0,
-            children: None,
-        }
-    }
-
-    pub fn add<'a, I>(&mut self, path: &mut I, count: u64)
-        where I: Iterator<Item=Sym>
-    {
-        self.count += count;
-        if let Some(child_name) = path.next() {
-            self.children
-                .get_or_insert_with(|| HashMap::new())
-                .entry(child_name.into())
-                .or_insert_with(|| Node::new())
-                .add(path, count);
-        }
-    }
-
-    pub fn depth(&self, min_count: u64, depth: u64) -> u64 {
-        if self.count < min_count {
-            return depth;
-        }
-        if let Some(children) = &self.children {
-            children.values().map(|c|c.depth(min_count, depth+1))
-                .max().unwrap_or(depth)
-        } else {
-            depth
-        }
-    }
-
-    #[allow(dead_code)]
-    pub fn print(&self, interner: &StringInterner<Sym>, name: &Sym, depth: usize) {
-        println!("{:pad$}{} {}", "", interner.resolve(*name).expect("lost interned string?"), self.count, pad=depth);
-        let children = if let Some(c) = &self.children { c } else { return };
-        let mut keys: Vec<Sym> = children.keys().cloned().collect();
-        keys.sort();
-        for k in keys {
-            children[&k].print(interner, &k, depth + 1);
-        }
-    }
-
-    #[allow(dead_code)]
-    pub fn gen_rects(&self, name: &Sym, depth: u64, offset: u64, buf: &mut Vec<Rect>) {
-        buf.push(Rect {
-            name: name.clone(),
-            count: self.count,
-            depth, offset,
-        });
-        let children = if let Some(c) = &self.children { c } else { return };
-        let mut keys: Vec<Sym> = children.keys().cloned().collect();
-        keys.sort();
-        let mut delta = 0;
-        for k in keys {
-            let child = &children[&k];
-            child.gen_rects(&k, depth + 1, offset + delta, buf);
-            delta += child.count;
-        }
-    }
-}
-
-#[derive(Debug)]
-struct Rect {
-    name: Sym,
-    count: u64,
-    depth: u64,
-    offset: u64,
-}
-
-struct Frame<'a> {
-    keys: Vec<Sym>,
-    start: u64,
-    offset: u64,
-    name: Sym,
-    node: &'a Node,
-}
-impl<'a> Frame<'a> {
-    pub fn new(node: &'a Node, name: &Sym, offset: u64) -> Frame<'a> {
-        let keys = if let Some(children) = &node.children {
-            let mut keys: Vec<Sym> = children.keys().cloned().collect();
-            keys.sort_by(|a, b| b.cmp(a));
-            keys
-        } else {
-            Vec::new()
-        };
-        Frame {
-            keys, node, offset,
-            start: offset,
-            name: name.clone(),
-        }
-    }
-}
-
-struct Rects<'a> {
-    stack: Vec<Frame<'a>>,
-}
-impl<'a> Rects<'a> {
-    pub fn new(node: &'a Node, name: &Sym) -> Rects<'a> {
-        Rects { stack: vec![Frame::new(node, name, 0)] }
-    }
-}
-impl<'a> Iterator for Rects<'a> {
-    type Item = Rect;
-    fn next(&mut self) -> Option<Self::Item> {
-        while let Some(mut current) = self.stack.pop() {
-            let depth = self.stack.len() as u64;
-            if let Some(key) = current.keys.pop() {
-                let child = &current.node.children.as_ref().unwrap()[&key];
-                let next = Frame::new(child, &key, current.offset);
-                current.offset += child.count;
-                self.stack.push(current);
-                self.stack.push(next);
-            } else {
-                return Some(Rect {
-                    name: current.name,
-                    count: current.node.count,
-                    offset: current.start,
-                    depth: depth,
-                });
-            }
-        }
-        None
-    }
-}
+use tree::{Node, Rects};
+use diagnostics::Diagnostics;
+use input::Format;
 
 fn main() {
-    let stdin = io::stdin();
-    let input = BufReader::new(stdin);
-    let mut invalid_lines = 0_u64;
+    let explore_mode = std::env::args().any(|a| a == "--explore");
+    let view_mode = std::env::args().any(|a| a == "--view");
+    let strict = std::env::args().any(|a| a == "--strict");
+    let format_arg = std::env::args()
+        .collect::<Vec<_>>()
+        .windows(2)
+        .find(|w| w[0] == "--format")
+        .map(|w| w[1].clone())
+        .unwrap_or_else(|| "auto".to_string());
+
+    let mut contents = String::new();
+    io::stdin().read_to_string(&mut contents).expect("failed to read stdin");
+
+    let format = match format_arg.as_str() {
+        "auto" => Format::sniff(&contents),
+        other => Format::parse(other).unwrap_or_else(|| {
+            eprintln!("unknown --format '{}', falling back to folded", other);
+            Format::Folded
+        }),
+    };
+
+    let mut diagnostics = Diagnostics::new(strict);
     let reverse = false;
 
     let mut interner = StringInterner::default();
     let mut root = Node::new();
-    for line_res in input.lines() {
-        let string = if let Ok(line) = line_res {
-            line
-        } else {
-            break;
-        };
-        let line = string.trim();
-        let stack;
-        let count_str;
-        if let Some(last) = line.rfind(' ') {
-            stack = &line[..last];
-            count_str = &line[last+1..];
-        } else {
-            invalid_lines += 1;
-            continue;
-        };
-
-        let count;
-        if let Ok(parsed) = count_str.parse() {
-            count = parsed;
-        } else {
-            invalid_lines += 1;
-            continue;
-        };
 
+    let stacks = match format {
+        Format::Folded => input::parse_folded(&contents, &mut diagnostics),
+        Format::Perf => input::parse_perf(&contents, &mut diagnostics),
+        Format::Dtrace => input::parse_dtrace(&contents, &mut diagnostics),
+    };
+    for stack in stacks {
+        let frames: Vec<Sym> = stack.frames.iter().map(|s| interner.get_or_intern(*s)).collect();
         if reverse {
-            root.add(&mut stack.rsplit(';')
-                .filter(|s|!s.is_empty())
-                .map(|s| interner.get_or_intern(s)), count);
+            root.add(&mut frames.into_iter().rev(), stack.count);
         } else {
-            root.add(&mut stack.split(';')
-                .filter(|s| !s.is_empty())
-                .map(|s| interner.get_or_intern(s)), count);
-        };
+            root.add(&mut frames.into_iter(), stack.count);
+        }
     }
 
     let name: Sym = interner.get_or_intern("all");
+    diagnostics.emit_all();
     if root.count == 0 {
         eprintln!("no valid stack counts provided!");
         return;
     }
 
+    if explore_mode {
+        if let Err(err) = explore::run(&root, &interner, name) {
+            eprintln!("explore mode failed: {}", err);
+        }
+        return;
+    }
+
+    if view_mode {
+        view::run(root, interner, name);
+    }
+
     let width = 1910.0_f32;
     let px_per_depth = 20.0_f32;
     let min_width = 0.1_f32;
@@ -240,7 +125,4 @@ r#"<g><title>{text} ({count} {count_name} {percent:.1}%)</title>
     }
     write!(&mut output, r#"</svg>"#);
     println!("{}", output);
-    if invalid_lines > 0 {
-        eprintln!("invalid lines: {}", invalid_lines);
-    }
 }
@@ -0,0 +1,143 @@
+use string_interner::Sym;
+use std::collections::HashMap;
+
+#[derive(Debug)]
+pub struct Node {
+    pub count: u64,
+    pub children: Option<HashMap<Sym, Node>>,
+}
+
+impl Node {
+    pub fn new() -> Node {
+        Node {
+            count: 0,
+            children: None,
+        }
+    }
+
+    pub fn add<'a, I>(&mut self, path: &mut I, count: u64)
+        where I: Iterator<Item=Sym>
+    {
+        self.count += count;
+        if let Some(child_name) = path.next() {
+            self.children
+                .get_or_insert_with(|| HashMap::new())
+                .entry(child_name.into())
+                .or_insert_with(|| Node::new())
+                .add(path, count);
+        }
+    }
+
+    pub fn depth(&self, min_count: u64, depth: u64) -> u64 {
+        if self.count < min_count {
+            return depth;
+        }
+        if let Some(children) = &self.children {
+            children.values().map(|c|c.depth(min_count, depth+1))
+                .max().unwrap_or(depth)
+        } else {
+            depth
+        }
+    }
+
+    /// Returns the children of this node sorted by descending sample count,
+    /// for UIs that want to present the "heaviest first" ordering.
+    pub fn children_by_count(&self) -> Vec<(Sym, &Node)> {
+        let children = if let Some(c) = &self.children { c } else { return Vec::new() };
+        let mut out: Vec<(Sym, &Node)> = children.iter().map(|(k, v)| (*k, v)).collect();
+        out.sort_by(|a, b| b.1.count.cmp(&a.1.count));
+        out
+    }
+
+    #[allow(dead_code)]
+    pub fn print(&self, interner: &string_interner::StringInterner<Sym>, name: &Sym, depth: usize) {
+        println!("{:pad$}{} {}", "", interner.resolve(*name).expect("lost interned string?"), self.count, pad=depth);
+        let children = if let Some(c) = &self.children { c } else { return };
+        let mut keys: Vec<Sym> = children.keys().cloned().collect();
+        keys.sort();
+        for k in keys {
+            children[&k].print(interner, &k, depth + 1);
+        }
+    }
+
+    pub fn gen_rects(&self, name: &Sym, depth: u64, offset: u64, buf: &mut Vec<Rect>) {
+        buf.push(Rect {
+            name: name.clone(),
+            count: self.count,
+            depth, offset,
+        });
+        let children = if let Some(c) = &self.children { c } else { return };
+        let mut keys: Vec<Sym> = children.keys().cloned().collect();
+        keys.sort();
+        let mut delta = 0;
+        for k in keys {
+            let child = &children[&k];
+            child.gen_rects(&k, depth + 1, offset + delta, buf);
+            delta += child.count;
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Rect {
+    pub name: Sym,
+    pub count: u64,
+    pub depth: u64,
+    pub offset: u64,
+}
+
+pub struct Frame<'a> {
+    keys: Vec<Sym>,
+    start: u64,
+    offset: u64,
+    name: Sym,
+    node: &'a Node,
+}
+impl<'a> Frame<'a> {
+    pub fn new(node: &'a Node, name: &Sym, offset: u64) -> Frame<'a> {
+        let keys = if let Some(children) = &node.children {
+            let mut keys: Vec<Sym> = children.keys().cloned().collect();
+            keys.sort_by(|a, b| b.cmp(a));
+            keys
+        } else {
+            Vec::new()
+        };
+        Frame {
+            keys, node, offset,
+            start: offset,
+            name: name.clone(),
+        }
+    }
+}
+
+pub struct Rects<'a> {
+    stack: Vec<Frame<'a>>,
+}
+impl<'a> Rects<'a> {
+    pub fn new(node: &'a Node, name: &Sym) -> Rects<'a> {
+        Rects { stack: vec![Frame::new(node, name, 0)] }
+    }
+}
+impl<'a> Iterator for Rects<'a> {
+    type Item = Rect;
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(mut current) = self.stack.pop() {
+            let depth = self.stack.len() as u64;
+            if let Some(key) = current.keys.pop() {
+                let child = &current.node.children.as_ref().unwrap()[&key];
+                let next = Frame::new(child, &key, current.offset);
+                current.offset += child.count;
+                self.stack.push(current);
+                self.stack.push(next);
+            } else {
+                return Some(Rect {
+                    name: current.name,
+                    count: current.node.count,
+                    offset: current.start,
+                    depth: depth,
+                });
+            }
+        }
+        None
+    }
+}
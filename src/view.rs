@@ -0,0 +1,253 @@
+//! `--view` mode: a resizable window (winit + `pixels`) that rasterizes the
+//! flamegraph into a pixel buffer instead of SVG, so very large profiles
+//! stay interactive without ever laying out a giant DOM of `<rect>`s.
+
+use pixels::{Pixels, SurfaceTexture};
+use winit::dpi::LogicalSize;
+use winit::event::{ElementState, Event, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::event_loop::{ControlFlow, EventLoop};
+use winit::window::WindowBuilder;
+
+use string_interner::{StringInterner, Sym};
+
+use crate::font;
+use crate::tree::{Node, Rects};
+
+const PX_PER_DEPTH: f32 = 20.0;
+const FRAME_COLOR: [u8; 4] = [200, 60, 40, 255];
+const BORDER_COLOR: [u8; 4] = [20, 20, 20, 255];
+const BG_COLOR: [u8; 4] = [30, 30, 30, 255];
+const LABEL_COLOR: [u8; 4] = [10, 10, 10, 255];
+const STATUS_BG_COLOR: [u8; 4] = [10, 10, 10, 255];
+const STATUS_TEXT_COLOR: [u8; 4] = [220, 220, 220, 255];
+const STATUS_BAR_HEIGHT: f32 = 16.0;
+const LABEL_MARGIN: f32 = 2.0;
+
+/// Maps counts (horizontal) and depth (vertical) onto window pixels.
+/// Pan/zoom only ever changes `scale_x` and `translate_x` - depth always
+/// maps linearly to rows of `PX_PER_DEPTH` pixels.
+struct Viewport {
+    scale_x: f32,
+    translate_x: f32,
+    max_depth: u64,
+}
+
+impl Viewport {
+    fn new(max_depth: u64) -> Viewport {
+        Viewport { scale_x: 1.0, translate_x: 0.0, max_depth }
+    }
+
+    fn x_to_screen(&self, x: f32, width: f32) -> f32 {
+        (x - self.translate_x) * self.scale_x * width
+    }
+
+    fn depth_to_y(&self, depth: u64) -> f32 {
+        ((self.max_depth - depth) as f32) * PX_PER_DEPTH
+    }
+
+    /// Zooms so that the horizontal extent `[start, start + span)` (as a
+    /// 0..1 fraction of total sample count) fills the window width.
+    fn zoom_to(&mut self, start: f32, span: f32) {
+        self.scale_x = 1.0 / span.max(1e-6);
+        self.translate_x = start;
+    }
+
+    fn reset(&mut self) {
+        self.scale_x = 1.0;
+        self.translate_x = 0.0;
+    }
+}
+
+struct FlatRect {
+    name: Sym,
+    count: u64,
+    start: f32,
+    end: f32,
+    depth: u64,
+}
+
+fn flatten(root: &Node, root_name: Sym) -> (Vec<FlatRect>, f32) {
+    let total = root.count as f32;
+    let mut out = Vec::new();
+    for rect in Rects::new(root, &root_name) {
+        out.push(FlatRect {
+            name: rect.name,
+            count: rect.count,
+            start: (rect.offset as f32) / total,
+            end: ((rect.offset + rect.count) as f32) / total,
+            depth: rect.depth,
+        });
+    }
+    (out, total)
+}
+
+/// `height` is the full window height; the bottom `STATUS_BAR_HEIGHT`
+/// pixels belong to the status bar, not the flamegraph, so hits there
+/// never resolve to a rect.
+fn rect_under(rects: &[FlatRect], viewport: &Viewport, width: f32, height: f32, px: f32, py: f32) -> Option<usize> {
+    if py >= height - STATUS_BAR_HEIGHT { return None; }
+    let depth_from_top = (py / PX_PER_DEPTH) as u64;
+    if depth_from_top > viewport.max_depth { return None; }
+    let depth = viewport.max_depth - depth_from_top;
+    rects.iter().position(|r| {
+        r.depth == depth
+            && viewport.x_to_screen(r.start, width) <= px
+            && px < viewport.x_to_screen(r.end, width)
+            && viewport.depth_to_y(r.depth) <= py
+            && py < viewport.depth_to_y(r.depth) + PX_PER_DEPTH
+    })
+}
+
+/// The flattened rect list plus what's needed to label and zoom them -
+/// bundled since `paint` needs all of it alongside the canvas and hover
+/// state.
+struct Scene<'a> {
+    rects: &'a [FlatRect],
+    viewport: &'a Viewport,
+    interner: &'a StringInterner<Sym>,
+    total: f32,
+}
+
+fn paint(canvas: &mut font::Canvas, scene: &Scene, hovered: Option<usize>) {
+    for px in canvas.frame.chunks_exact_mut(4) {
+        px.copy_from_slice(&BG_COLOR);
+    }
+
+    let (width, height) = (canvas.width, canvas.height);
+    let width_f = width as f32;
+    for (i, rect) in scene.rects.iter().enumerate() {
+        let x0 = scene.viewport.x_to_screen(rect.start, width_f).max(0.0);
+        let x1 = scene.viewport.x_to_screen(rect.end, width_f).min(width_f);
+        if x1 <= x0 { continue; }
+        let y0 = scene.viewport.depth_to_y(rect.depth);
+        let y1 = y0 + PX_PER_DEPTH - 1.0;
+
+        let color = if Some(i) == hovered { BORDER_COLOR } else { FRAME_COLOR };
+        for y in (y0 as u32)..=(y1 as u32).min(height.saturating_sub(1)) {
+            for x in (x0 as u32)..(x1 as u32).min(width) {
+                let idx = ((y * width + x) * 4) as usize;
+                if idx + 4 <= canvas.frame.len() {
+                    canvas.frame[idx..idx + 4].copy_from_slice(&color);
+                }
+            }
+        }
+
+        let name = scene.interner.resolve(rect.name).unwrap_or("?");
+        canvas.draw_text(
+            x0 + LABEL_MARGIN, y0 + (PX_PER_DEPTH - font::GLYPH_H as f32) / 2.0,
+            x1 - LABEL_MARGIN,
+            name, LABEL_COLOR, 1,
+        );
+    }
+
+    let bar_y = (height as f32 - STATUS_BAR_HEIGHT).max(0.0) as u32;
+    for y in bar_y..height {
+        for x in 0..width {
+            let idx = ((y * width + x) * 4) as usize;
+            if idx + 4 <= canvas.frame.len() {
+                canvas.frame[idx..idx + 4].copy_from_slice(&STATUS_BG_COLOR);
+            }
+        }
+    }
+
+    let status = match hovered {
+        Some(i) => {
+            let rect = &scene.rects[i];
+            let name = scene.interner.resolve(rect.name).unwrap_or("?");
+            format!("{} ({} {:.1}%)", name, rect.count, 100.0 * (rect.end - rect.start))
+        }
+        None => format!("{} samples total - click a frame to zoom, right-click to reset", scene.total as u64),
+    };
+    canvas.draw_text(
+        LABEL_MARGIN, bar_y as f32 + (STATUS_BAR_HEIGHT - font::GLYPH_H as f32) / 2.0,
+        width as f32 - LABEL_MARGIN,
+        &status, STATUS_TEXT_COLOR, 1,
+    );
+}
+
+/// Runs the windowed viewer until the user closes it.
+pub fn run(root: Node, interner: StringInterner<Sym>, root_name: Sym) -> ! {
+    let max_depth = root.depth(0, 0);
+    let (rects, total) = flatten(&root, root_name);
+
+    let event_loop = EventLoop::new();
+    let window = WindowBuilder::new()
+        .with_title("flamegraph-rs --view")
+        .with_inner_size(LogicalSize::new(
+            1280.0,
+            (max_depth as f64 + 1.0) * PX_PER_DEPTH as f64 + STATUS_BAR_HEIGHT as f64,
+        ))
+        .build(&event_loop)
+        .expect("failed to create window");
+
+    let mut size = window.inner_size();
+    let surface_texture = SurfaceTexture::new(size.width, size.height, &window);
+    let mut pixels = Pixels::new(size.width, size.height, surface_texture)
+        .expect("failed to create pixel buffer");
+
+    let mut viewport = Viewport::new(max_depth);
+    let mut cursor = (0.0_f32, 0.0_f32);
+    let mut dragging = false;
+    let mut hovered = None;
+
+    event_loop.run(move |event, _, control_flow| {
+        *control_flow = ControlFlow::Wait;
+        match event {
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
+                WindowEvent::Resized(new_size) => {
+                    size = new_size;
+                    let _ = pixels.resize_surface(size.width, size.height);
+                    let _ = pixels.resize_buffer(size.width, size.height);
+                    window.request_redraw();
+                }
+                WindowEvent::CursorMoved { position, .. } => {
+                    let (px, py) = (position.x as f32, position.y as f32);
+                    if dragging {
+                        let dx = (px - cursor.0) / (size.width as f32) / viewport.scale_x;
+                        viewport.translate_x -= dx;
+                    }
+                    cursor = (px, py);
+                    hovered = rect_under(&rects, &viewport, size.width as f32, size.height as f32, px, py);
+                    window.request_redraw();
+                }
+                WindowEvent::MouseInput { state, button, .. } => {
+                    match (button, state) {
+                        (MouseButton::Left, ElementState::Pressed) => {
+                            if let Some(i) = hovered {
+                                let r = &rects[i];
+                                viewport.zoom_to(r.start, r.end - r.start);
+                                window.request_redraw();
+                            } else {
+                                dragging = true;
+                            }
+                        }
+                        (MouseButton::Left, ElementState::Released) => dragging = false,
+                        (MouseButton::Right, ElementState::Pressed) => {
+                            viewport.reset();
+                            window.request_redraw();
+                        }
+                        _ => {}
+                    }
+                }
+                WindowEvent::MouseWheel { delta, .. } => {
+                    let amount = match delta {
+                        MouseScrollDelta::LineDelta(_, y) => y,
+                        MouseScrollDelta::PixelDelta(p) => (p.y / 20.0) as f32,
+                    };
+                    let zoom = (1.0 + amount * 0.1).max(0.1);
+                    viewport.scale_x *= zoom;
+                    window.request_redraw();
+                }
+                _ => {}
+            },
+            Event::RedrawRequested(_) => {
+                let mut canvas = font::Canvas::new(pixels.frame_mut(), size.width, size.height);
+                let scene = Scene { rects: &rects, viewport: &viewport, interner: &interner, total };
+                paint(&mut canvas, &scene, hovered);
+                let _ = pixels.render();
+            }
+            _ => {}
+        }
+    });
+}